@@ -2,6 +2,7 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use spin::Mutex;
 use lazy_static::lazy_static;
+use crate::hash;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
@@ -13,11 +14,22 @@ pub enum Role {
 #[derive(Debug, Clone)]
 pub struct User {
     pub username: String,
+    /// Hex-encoded digest from `hash::hash_password`, never the raw password.
     pub password_hash: String,
+    /// Hex-encoded per-user salt used to compute `password_hash`.
+    pub salt: String,
     pub role: Role,
     pub uid: u32,
 }
 
+/// Salts `password`, runs it through the iterated mixing hash and hex-encodes
+/// both halves for storage on a `User`.
+fn hash_for_storage(password: &str) -> (String, String) {
+    let salt = hash::random_salt(hash::SALT_LEN);
+    let digest = hash::hash_password(password.as_bytes(), &salt, hash::DEFAULT_ITERATIONS);
+    (hash::to_hex(&salt), hash::to_hex(&digest.to_le_bytes()))
+}
+
 pub struct AuthManager {
     pub users: Vec<User>,
     pub current_user: Option<User>,
@@ -27,14 +39,16 @@ pub struct AuthManager {
 impl AuthManager {
     pub fn new() -> Self {
         let mut users = Vec::new();
-        // Default admin account for Andre
+        // Default admin account for Andre, migrated to a salted hash at construction
+        let (salt, password_hash) = hash_for_storage("admin123");
         users.push(User {
             username: String::from("andre"),
-            password_hash: String::from("admin123"),
+            password_hash,
+            salt,
             role: Role::Admin,
             uid: 0,
         });
-        
+
         AuthManager {
             users,
             current_user: None,
@@ -50,9 +64,11 @@ impl AuthManager {
         }
 
         let new_uid = self.next_uid;
+        let (salt, password_hash) = hash_for_storage(password);
         self.users.push(User {
             username: String::from(username),
-            password_hash: String::from(password),
+            password_hash,
+            salt,
             role: Role::Standard,
             uid: new_uid,
         });
@@ -62,13 +78,29 @@ impl AuthManager {
     }
 
     pub fn login(&mut self, username: &str, password: &str) -> bool {
+        let mut matched = None;
         for user in &self.users {
-            if user.username == username && user.password_hash == password {
-                self.current_user = Some(user.clone());
-                return true;
+            if user.username == username {
+                matched = Some(user.clone());
+                break;
             }
         }
-        false
+
+        let user = match matched {
+            Some(user) => user,
+            None => return false,
+        };
+
+        let salt = hash::from_hex(&user.salt);
+        let digest = hash::hash_password(password.as_bytes(), &salt, hash::DEFAULT_ITERATIONS);
+        let computed = hash::to_hex(&digest.to_le_bytes());
+
+        if hash::constant_time_eq(computed.as_bytes(), user.password_hash.as_bytes()) {
+            self.current_user = Some(user);
+            true
+        } else {
+            false
+        }
     }
 
     #[allow(dead_code)]
@@ -87,6 +119,12 @@ impl AuthManager {
         self.current_user.as_ref().map(|u| u.uid).unwrap_or(1000)
     }
 
+    /// Looks up `uid` among known users and reports whether it belongs to an admin.
+    /// Used by the filesystem to decide whether to bypass permission checks.
+    pub fn is_admin(&self, uid: u32) -> bool {
+        self.users.iter().any(|u| u.uid == uid && u.role == Role::Admin)
+    }
+
     pub fn delete_user(&mut self, username: &str) -> Result<(), &'static str> {
         // Prevent deleting the admin or the currently logged-in user
         if username == "andre" {
@@ -1,52 +1,109 @@
-use crate::task::Task;
-use alloc::collections::VecDeque;
-use core::task::{Context, Poll, Waker, RawWaker, RawWakerVTable};
+use crate::task::{Task, TaskId};
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::task::Wake;
+use core::task::{Context, Poll, Waker};
+use crossbeam_queue::ArrayQueue;
+
+/// Maximum number of tasks that can be simultaneously queued as "ready".
+/// Generous headroom over our handful of background tasks.
+const QUEUE_CAPACITY: usize = 100;
 
 pub struct Executor {
-    task_queue: VecDeque<Task>,
+    tasks: BTreeMap<TaskId, Task>,
+    task_queue: Arc<ArrayQueue<TaskId>>,
+    waker_cache: BTreeMap<TaskId, Waker>,
 }
 
 impl Executor {
     pub fn new() -> Self {
         Executor {
-            task_queue: VecDeque::new(),
+            tasks: BTreeMap::new(),
+            task_queue: Arc::new(ArrayQueue::new(QUEUE_CAPACITY)),
+            waker_cache: BTreeMap::new(),
         }
     }
 
     pub fn spawn(&mut self, task: Task) {
-        self.task_queue.push_back(task);
+        let task_id = task.id;
+        if self.tasks.insert(task_id, task).is_some() {
+            panic!("task with same ID already spawned");
+        }
+        self.task_queue.push(task_id).expect("task_queue full");
     }
 
     pub fn run(&mut self) -> ! {
         loop {
             self.run_ready_tasks();
-            x86_64::instructions::hlt(); 
+            self.sleep_if_idle();
         }
     }
 
     fn run_ready_tasks(&mut self) {
-        let mut remaining_tasks = self.task_queue.len();
-        
-        while let Some(mut task) = self.task_queue.pop_front() {
-            let waker = dummy_waker();
-            let mut context = Context::from_waker(&waker);
-            
+        // Split the borrow up front so the waker closure below doesn't need
+        // to re-borrow `self` while `tasks` is already borrowed mutably.
+        let Self { tasks, task_queue, waker_cache } = self;
+
+        while let Some(task_id) = task_queue.pop() {
+            let task = match tasks.get_mut(&task_id) {
+                Some(task) => task,
+                None => continue, // task already completed and was dropped
+            };
+            let waker = waker_cache
+                .entry(task_id)
+                .or_insert_with(|| TaskWaker::new(task_id, task_queue.clone()));
+            let mut context = Context::from_waker(waker);
+
             match task.future.as_mut().poll(&mut context) {
-                Poll::Ready(()) => {} // Tâche terminée, on ne la repousse pas
-                Poll::Pending => {
-                    self.task_queue.push_back(task); // Pas fini, on la remet en queue
+                Poll::Ready(()) => {
+                    tasks.remove(&task_id);
+                    waker_cache.remove(&task_id);
                 }
+                Poll::Pending => {}
             }
+        }
+    }
+
+    /// Halts the CPU until the next interrupt, unless a wake already landed
+    /// a task back in the queue. Disabling interrupts before the emptiness
+    /// check and re-enabling them atomically with `hlt` closes the race
+    /// where an interrupt's `wake()` fires between the check and the halt.
+    fn sleep_if_idle(&self) {
+        use x86_64::instructions::interrupts::{self, enable_and_hlt};
 
-            remaining_tasks -= 1;
-            if remaining_tasks == 0 { break; }
+        interrupts::disable();
+        if self.task_queue.is_empty() {
+            enable_and_hlt();
+        } else {
+            interrupts::enable();
         }
     }
 }
 
-fn dummy_waker() -> Waker {
-    fn no_op(_: *const ()) {}
-    fn clone(_: *const ()) -> RawWaker { RawWaker::new(core::ptr::null(), &VTABLE) }
-    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
-    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
-}
\ No newline at end of file
+struct TaskWaker {
+    task_id: TaskId,
+    task_queue: Arc<ArrayQueue<TaskId>>,
+}
+
+impl TaskWaker {
+    fn new(task_id: TaskId, task_queue: Arc<ArrayQueue<TaskId>>) -> Waker {
+        Waker::from(Arc::new(TaskWaker { task_id, task_queue }))
+    }
+
+    fn wake_task(&self) {
+        // If a task wakes itself mid-poll, this re-queues it immediately;
+        // the next iteration of `run_ready_tasks` picks it back up before
+        // `sleep_if_idle` ever sees an empty queue, so it can't livelock.
+        self.task_queue.push(self.task_id).expect("task_queue full");
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_task();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_task();
+    }
+}
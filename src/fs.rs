@@ -9,6 +9,47 @@ pub enum NodeType {
     File,
     Directory,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsError {
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    NotADirectory,
+    NotAFile,
+    InvalidPath,
+    CorruptImage,
+}
+
+impl FsError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FsError::NotFound => "No such file or directory",
+            FsError::AlreadyExists => "File or directory already exists",
+            FsError::PermissionDenied => "Permission denied",
+            FsError::NotADirectory => "Not a directory",
+            FsError::NotAFile => "Not a file",
+            FsError::InvalidPath => "Invalid path",
+            FsError::CorruptImage => "Corrupt or truncated filesystem image",
+        }
+    }
+}
+
+/// Unix-style rwx bits, but only owner/other (no group, we have no group concept).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMode {
+    Read,
+    Write,
+    Execute,
+}
+
+const OWNER_READ: u16 = 0o400;
+const OWNER_WRITE: u16 = 0o200;
+const OWNER_EXEC: u16 = 0o100;
+const OTHER_READ: u16 = 0o004;
+const OTHER_WRITE: u16 = 0o002;
+const OTHER_EXEC: u16 = 0o001;
+
 #[allow(dead_code)]
 pub struct Inode {
     pub uid: u32,
@@ -16,6 +57,32 @@ pub struct Inode {
     pub node_type: NodeType,
 }
 
+impl Inode {
+    /// Checks `want` against this inode's owner/other bits for `uid`, unless
+    /// `is_admin` bypasses the check entirely.
+    fn check_access(&self, uid: u32, is_admin: bool, want: AccessMode) -> Result<(), FsError> {
+        if is_admin {
+            return Ok(());
+        }
+
+        let is_owner = self.uid == uid;
+        let bit = match (is_owner, want) {
+            (true, AccessMode::Read) => OWNER_READ,
+            (true, AccessMode::Write) => OWNER_WRITE,
+            (true, AccessMode::Execute) => OWNER_EXEC,
+            (false, AccessMode::Read) => OTHER_READ,
+            (false, AccessMode::Write) => OTHER_WRITE,
+            (false, AccessMode::Execute) => OTHER_EXEC,
+        };
+
+        if self.permissions & bit != 0 {
+            Ok(())
+        } else {
+            Err(FsError::PermissionDenied)
+        }
+    }
+}
+
 pub struct File {
     pub inode: Inode,
     pub data: Vec<u8>,
@@ -31,13 +98,94 @@ pub enum FsNode {
     Directory(Directory),
 }
 
+impl FsNode {
+    fn inode(&self) -> &Inode {
+        match self {
+            FsNode::File(f) => &f.inode,
+            FsNode::Directory(d) => &d.inode,
+        }
+    }
+
+    fn inode_mut(&mut self) -> &mut Inode {
+        match self {
+            FsNode::File(f) => &mut f.inode,
+            FsNode::Directory(d) => &mut d.inode,
+        }
+    }
+}
+
 pub struct RamFileSystem {
     pub root: Directory,
     pub cwd: Vec<String>,
+    /// Soft-deleted nodes, keyed by a monotonically increasing deletion
+    /// sequence number, alongside the absolute path they were removed from.
+    /// This side map is the ".trash" bin; there's no real `.trash` directory
+    /// entry in `root`.
+    trash: BTreeMap<u64, (Vec<String>, FsNode)>,
+    next_trash_seq: u64,
 }
 
 lazy_static! {
     pub static ref FS: Mutex<RamFileSystem> = Mutex::new(RamFileSystem::new());
+
+    /// Holds the most recent `save_image` blob for the `snapshot`/
+    /// `restore-snapshot` shell commands to round-trip through. There's no
+    /// disk driver yet, so this in-memory slot is the only place a snapshot
+    /// can live.
+    static ref IMAGE_SLOT: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+}
+
+/// Snapshots the live filesystem into `IMAGE_SLOT`.
+pub fn save_snapshot() {
+    let image = FS.lock().save_image();
+    *IMAGE_SLOT.lock() = Some(image);
+}
+
+/// Replaces the live filesystem with whatever's in `IMAGE_SLOT`.
+pub fn load_snapshot() -> Result<(), FsError> {
+    let image = IMAGE_SLOT.lock().clone().ok_or(FsError::NotFound)?;
+    let restored = RamFileSystem::load_image(&image)?;
+    *FS.lock() = restored;
+    Ok(())
+}
+
+/// Looks up whether `uid` is an admin via the auth subsystem.
+fn is_admin(uid: u32) -> bool {
+    crate::auth::AUTH.lock().is_admin(uid)
+}
+
+/// Parses `path` (absolute or relative to `cwd`) into a normalized segment
+/// list, collapsing `.`/`..` and rejecting traversal above root.
+fn resolve_path(cwd: &[String], path: &str) -> Result<Vec<String>, FsError> {
+    let mut segments: Vec<String> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        cwd.to_vec()
+    };
+
+    for part in path.split('/') {
+        match part {
+            "" | "." => continue,
+            ".." => {
+                if segments.pop().is_none() {
+                    return Err(FsError::InvalidPath);
+                }
+            }
+            seg => segments.push(seg.to_string()),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Splits a resolved segment list into (parent segments, final name).
+/// Errors if `path` resolved to the root itself (no name component).
+fn split_parent(segments: Vec<String>) -> Result<(Vec<String>, String), FsError> {
+    let mut segments = segments;
+    match segments.pop() {
+        Some(name) => Ok((segments, name)),
+        None => Err(FsError::InvalidPath),
+    }
 }
 
 impl RamFileSystem {
@@ -48,12 +196,17 @@ impl RamFileSystem {
                 entries: BTreeMap::new(),
             },
             cwd: Vec::new(),
+            trash: BTreeMap::new(),
+            next_trash_seq: 0,
         }
     }
 
-    fn get_current_dir(&self) -> &Directory {
+    /// Best-effort, permission-oblivious descent from root along `segments`,
+    /// stopping short if a segment is missing or not a directory. Used only
+    /// by read-only introspection (`ls`) that predates ownership.
+    fn get_current_dir(&self, segments: &[String]) -> &Directory {
         let mut curr = &self.root;
-        for segment in &self.cwd {
+        for segment in segments {
             if let Some(FsNode::Directory(next_dir)) = curr.entries.get(segment) {
                 curr = next_dir;
             }
@@ -61,23 +214,64 @@ impl RamFileSystem {
         curr
     }
 
-    /// CORRECTION : Navigation mutable compatible avec le Borrow Checker Rust
-    fn get_current_dir_mut(&mut self) -> &mut Directory {
+    /// Permission-checked descent: requires Execute on every directory
+    /// crossed (including the final one), admins bypass.
+    fn walk(&self, segments: &[String], uid: u32, admin: bool) -> Result<&Directory, FsError> {
+        let mut curr = &self.root;
+        for seg in segments {
+            curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+            curr = match curr.entries.get(seg) {
+                Some(FsNode::Directory(dir)) => dir,
+                Some(FsNode::File(_)) => return Err(FsError::NotADirectory),
+                None => return Err(FsError::NotFound),
+            };
+        }
+        curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+        Ok(curr)
+    }
+
+    /// Mutable counterpart of `walk`.
+    fn walk_mut(&mut self, segments: &[String], uid: u32, admin: bool) -> Result<&mut Directory, FsError> {
         let mut curr = &mut self.root;
-        for segment in self.cwd.iter() {
-            // Cette astuce de "re-binding" permet de descendre sans bloquer les mutables
-            let next = if let Some(FsNode::Directory(ref mut next_dir)) = curr.entries.get_mut(segment) {
-                next_dir as *mut Directory
-            } else {
-                curr as *mut Directory
+        for seg in segments {
+            curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+            let next = match curr.entries.get_mut(seg) {
+                Some(FsNode::Directory(dir)) => dir as *mut Directory,
+                Some(FsNode::File(_)) => return Err(FsError::NotADirectory),
+                None => return Err(FsError::NotFound),
             };
-            unsafe { curr = &mut *next; }
+            curr = unsafe { &mut *next };
         }
-        curr
+        curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+        Ok(curr)
+    }
+
+    /// Like `walk_mut`, but creates any missing directory along the way
+    /// (the `mkdir -p` mode), owned by `uid`.
+    fn walk_mut_create(&mut self, segments: &[String], uid: u32, admin: bool) -> Result<&mut Directory, FsError> {
+        let mut curr = &mut self.root;
+        for seg in segments {
+            curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+            if !curr.entries.contains_key(seg) {
+                curr.inode.check_access(uid, admin, AccessMode::Write)?;
+                curr.entries.insert(seg.clone(), FsNode::Directory(Directory {
+                    inode: Inode { uid, permissions: 0o755, node_type: NodeType::Directory },
+                    entries: BTreeMap::new(),
+                }));
+            }
+            let next = match curr.entries.get_mut(seg) {
+                Some(FsNode::Directory(dir)) => dir as *mut Directory,
+                Some(FsNode::File(_)) => return Err(FsError::NotADirectory),
+                None => unreachable!("just inserted or already present"),
+            };
+            curr = unsafe { &mut *next };
+        }
+        curr.inode.check_access(uid, admin, AccessMode::Execute)?;
+        Ok(curr)
     }
 
     pub fn ls(&self) -> Vec<(String, NodeType)> {
-        let current_dir = self.get_current_dir();
+        let current_dir = self.get_current_dir(&self.cwd);
         current_dir.entries.iter()
             .map(|(name, node)| {
                 let t = match node {
@@ -89,59 +283,186 @@ impl RamFileSystem {
             .collect()
     }
 
-    pub fn write_file(&mut self, name: &str, content: &str, uid: u32) -> Result<(), &str> {
-        let current_dir = self.get_current_dir_mut();
+    pub fn write_file(&mut self, path: &str, content: &str, uid: u32) -> Result<(), FsError> {
+        let admin = is_admin(uid);
+        let segments = resolve_path(&self.cwd, path)?;
+        let (parent, name) = split_parent(segments)?;
+        let parent_dir = self.walk_mut(&parent, uid, admin)?;
         let data = Vec::from(content.as_bytes());
-        
+
+        match parent_dir.entries.get(&name) {
+            Some(FsNode::File(f)) => f.inode.check_access(uid, admin, AccessMode::Write)?,
+            Some(FsNode::Directory(_)) => return Err(FsError::NotAFile),
+            // A brand-new entry needs Write on the parent directory itself,
+            // not just Execute to traverse into it.
+            None => parent_dir.inode.check_access(uid, admin, AccessMode::Write)?,
+        }
+
         let file_node = FsNode::File(File {
             inode: Inode { uid, permissions: 0o644, node_type: NodeType::File },
             data,
         });
 
-        current_dir.entries.insert(name.to_string(), file_node);
+        parent_dir.entries.insert(name, file_node);
         Ok(())
     }
 
-    pub fn read_file(&self, name: &str) -> Option<String> {
-        let current_dir = self.get_current_dir();
-        if let Some(FsNode::File(f)) = current_dir.entries.get(name) {
-            Some(String::from_utf8_lossy(&f.data).into_owned())
-        } else {
-            None
+    pub fn read_file(&self, path: &str, uid: u32) -> Result<String, FsError> {
+        let admin = is_admin(uid);
+        let segments = resolve_path(&self.cwd, path)?;
+        let (parent, name) = split_parent(segments)?;
+        let parent_dir = self.walk(&parent, uid, admin)?;
+        match parent_dir.entries.get(&name) {
+            Some(FsNode::File(f)) => {
+                f.inode.check_access(uid, admin, AccessMode::Read)?;
+                Ok(String::from_utf8_lossy(&f.data).into_owned())
+            }
+            Some(FsNode::Directory(_)) => Err(FsError::NotAFile),
+            None => Err(FsError::NotFound),
+        }
+    }
+
+    /// Soft-deletes the file at `path` into the trash bin.
+    pub fn remove_file(&mut self, path: &str, uid: u32) -> Result<(), FsError> {
+        self.soft_delete(path, uid, false)
+    }
+
+    /// Soft-deletes the directory at `path` into the trash bin.
+    pub fn remove_dir(&mut self, path: &str, uid: u32) -> Result<(), FsError> {
+        self.soft_delete(path, uid, true)
+    }
+
+    fn soft_delete(&mut self, path: &str, uid: u32, want_dir: bool) -> Result<(), FsError> {
+        let admin = is_admin(uid);
+        let segments = resolve_path(&self.cwd, path)?;
+        let (parent, name) = split_parent(segments.clone())?;
+
+        let removed = {
+            let parent_dir = self.walk_mut(&parent, uid, admin)?;
+            match parent_dir.entries.get(&name) {
+                Some(node) => {
+                    let is_dir = matches!(node, FsNode::Directory(_));
+                    if is_dir != want_dir {
+                        return Err(if want_dir { FsError::NotADirectory } else { FsError::NotAFile });
+                    }
+                    node.inode().check_access(uid, admin, AccessMode::Write)?;
+                }
+                None => return Err(FsError::NotFound),
+            }
+            parent_dir.entries.remove(&name).expect("entry presence just checked above")
+        };
+
+        let seq = self.next_trash_seq;
+        self.next_trash_seq += 1;
+        self.trash.insert(seq, (segments, removed));
+        Ok(())
+    }
+
+    /// Lists everything currently in the trash bin: its deletion sequence
+    /// number, the absolute path it was removed from, and its node type.
+    pub fn list_trash(&self) -> Vec<(u64, String, NodeType)> {
+        self.trash.iter().map(|(seq, (path, node))| {
+            let full_path = alloc::format!("/{}", path.join("/"));
+            let node_type = match node {
+                FsNode::File(_) => NodeType::File,
+                FsNode::Directory(_) => NodeType::Directory,
+            };
+            (*seq, full_path, node_type)
+        }).collect()
+    }
+
+    /// Restores a trashed node to its original path, creating missing parent
+    /// directories along the way. Errors (and leaves the item in the trash)
+    /// if the original location is now occupied or its parent can't be
+    /// reconstructed.
+    pub fn restore(&mut self, seq: u64) -> Result<(), FsError> {
+        let (path, node) = self.trash.remove(&seq).ok_or(FsError::NotFound)?;
+
+        let (parent, name) = match split_parent(path.clone()) {
+            Ok(v) => v,
+            Err(e) => { self.trash.insert(seq, (path, node)); return Err(e); }
+        };
+        let owner = node.inode().uid;
+        let parent_dir = match self.walk_mut_create(&parent, owner, true) {
+            Ok(dir) => dir,
+            Err(e) => { self.trash.insert(seq, (path, node)); return Err(e); }
+        };
+        if parent_dir.entries.contains_key(&name) {
+            self.trash.insert(seq, (path, node));
+            return Err(FsError::AlreadyExists);
         }
+        parent_dir.entries.insert(name, node);
+        Ok(())
     }
 
-    pub fn remove_file(&mut self, name: &str) -> bool {
-        let current_dir = self.get_current_dir_mut();
-        current_dir.entries.remove(name).is_some()
+    /// Permanently deletes everything in the trash bin.
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
     }
 
-    pub fn mkdir(&mut self, name: &str, uid: u32) -> Result<(), &str> {
-        let current_dir = self.get_current_dir_mut();
-        if current_dir.entries.contains_key(name) {
-            return Err("Le nom existe déjà");
+    /// Creates a single directory. Pass `recursive = true` for `mkdir -p`
+    /// semantics (create missing parents, don't error if it already exists).
+    pub fn mkdir(&mut self, path: &str, uid: u32, recursive: bool) -> Result<(), FsError> {
+        let admin = is_admin(uid);
+        let segments = resolve_path(&self.cwd, path)?;
+
+        if recursive {
+            self.walk_mut_create(&segments, uid, admin)?;
+            return Ok(());
+        }
+
+        let (parent, name) = split_parent(segments)?;
+        let parent_dir = self.walk_mut(&parent, uid, admin)?;
+        if parent_dir.entries.contains_key(&name) {
+            return Err(FsError::AlreadyExists);
         }
-        let new_dir = FsNode::Directory(Directory {
+        parent_dir.inode.check_access(uid, admin, AccessMode::Write)?;
+        parent_dir.entries.insert(name, FsNode::Directory(Directory {
             inode: Inode { uid, permissions: 0o755, node_type: NodeType::Directory },
             entries: BTreeMap::new(),
-        });
-        current_dir.entries.insert(name.to_string(), new_dir);
+        }));
         Ok(())
     }
 
-    pub fn cd(&mut self, path: &str) -> Result<(), &str> {
-        match path {
-            "/" => { self.cwd.clear(); Ok(()) },
-            ".." => { self.cwd.pop(); Ok(()) },
-            _ => {
-                let current_dir = self.get_current_dir();
-                if let Some(FsNode::Directory(_)) = current_dir.entries.get(path) {
-                    self.cwd.push(path.to_string());
-                    Ok(())
-                } else {
-                    Err("Dossier introuvable")
-                }
+    pub fn cd(&mut self, path: &str, uid: u32) -> Result<(), FsError> {
+        let admin = is_admin(uid);
+        let segments = resolve_path(&self.cwd, path)?;
+        self.walk(&segments, uid, admin)?;
+        self.cwd = segments;
+        Ok(())
+    }
+
+    /// Admin-only: changes the rwx bits of the entry at `path`.
+    pub fn chmod(&mut self, path: &str, permissions: u16, uid: u32) -> Result<(), FsError> {
+        if !is_admin(uid) {
+            return Err(FsError::PermissionDenied);
+        }
+        let segments = resolve_path(&self.cwd, path)?;
+        let (parent, name) = split_parent(segments)?;
+        let parent_dir = self.walk_mut(&parent, uid, true)?;
+        match parent_dir.entries.get_mut(&name) {
+            Some(node) => {
+                node.inode_mut().permissions = permissions;
+                Ok(())
             }
+            None => Err(FsError::NotFound),
+        }
+    }
+
+    /// Admin-only: reassigns the owning uid of the entry at `path`.
+    pub fn chown(&mut self, path: &str, new_uid: u32, uid: u32) -> Result<(), FsError> {
+        if !is_admin(uid) {
+            return Err(FsError::PermissionDenied);
+        }
+        let segments = resolve_path(&self.cwd, path)?;
+        let (parent, name) = split_parent(segments)?;
+        let parent_dir = self.walk_mut(&parent, uid, true)?;
+        match parent_dir.entries.get_mut(&name) {
+            Some(node) => {
+                node.inode_mut().uid = new_uid;
+                Ok(())
+            }
+            None => Err(FsError::NotFound),
         }
     }
 
@@ -162,4 +483,143 @@ impl RamFileSystem {
         }
         traverse(&self.root)
     }
-}
\ No newline at end of file
+
+    /// Serializes the whole tree into a flat, self-describing byte image
+    /// that `load_image` can round-trip.
+    pub fn save_image(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.root.inode.uid.to_le_bytes());
+        buf.extend_from_slice(&self.root.inode.permissions.to_le_bytes());
+        write_entries(&self.root, &mut buf);
+        buf
+    }
+
+    /// Rebuilds a `RamFileSystem` from a `save_image` blob, rejecting
+    /// truncated or out-of-bounds input.
+    pub fn load_image(data: &[u8]) -> Result<Self, FsError> {
+        let mut r = ImageReader::new(data);
+        let uid = r.u32()?;
+        let permissions = r.u16()?;
+        let entries = read_entries(&mut r)?;
+        Ok(RamFileSystem {
+            root: Directory {
+                inode: Inode { uid, permissions, node_type: NodeType::Directory },
+                entries,
+            },
+            cwd: Vec::new(),
+            trash: BTreeMap::new(),
+            next_trash_seq: 0,
+        })
+    }
+}
+
+const TAG_FILE: u8 = 0;
+const TAG_DIR: u8 = 1;
+
+fn write_name(name: &str, buf: &mut Vec<u8>) {
+    let bytes = name.as_bytes();
+    buf.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+/// Writes `dir`'s children: a `u32` count, then each entry as
+/// `tag | uid(4) | permissions(2) | name(len-prefixed) | payload`, where the
+/// payload is a length-prefixed data blob for files and a recursive child
+/// list for directories. Reuses the recursion pattern from `get_stats`.
+fn write_entries(dir: &Directory, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&(dir.entries.len() as u32).to_le_bytes());
+    for (name, node) in &dir.entries {
+        match node {
+            FsNode::File(f) => {
+                buf.push(TAG_FILE);
+                buf.extend_from_slice(&f.inode.uid.to_le_bytes());
+                buf.extend_from_slice(&f.inode.permissions.to_le_bytes());
+                write_name(name, buf);
+                buf.extend_from_slice(&(f.data.len() as u32).to_le_bytes());
+                buf.extend_from_slice(&f.data);
+            }
+            FsNode::Directory(d) => {
+                buf.push(TAG_DIR);
+                buf.extend_from_slice(&d.inode.uid.to_le_bytes());
+                buf.extend_from_slice(&d.inode.permissions.to_le_bytes());
+                write_name(name, buf);
+                write_entries(d, buf);
+            }
+        }
+    }
+}
+
+/// Bounds-checked cursor over a `save_image` blob.
+struct ImageReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ImageReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ImageReader { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], FsError> {
+        let end = self.pos.checked_add(n).ok_or(FsError::CorruptImage)?;
+        if end > self.data.len() {
+            return Err(FsError::CorruptImage);
+        }
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, FsError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FsError> {
+        let bytes: [u8; 2] = self.take(2)?.try_into().map_err(|_| FsError::CorruptImage)?;
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u32(&mut self) -> Result<u32, FsError> {
+        let bytes: [u8; 4] = self.take(4)?.try_into().map_err(|_| FsError::CorruptImage)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn name(&mut self) -> Result<String, FsError> {
+        let len = self.u16()? as usize;
+        let bytes = self.take(len)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+fn read_entries(r: &mut ImageReader) -> Result<BTreeMap<String, FsNode>, FsError> {
+    let count = r.u32()?;
+    let mut entries = BTreeMap::new();
+    for _ in 0..count {
+        let tag = r.u8()?;
+        let uid = r.u32()?;
+        let permissions = r.u16()?;
+        let name = r.name()?;
+
+        let node = match tag {
+            TAG_FILE => {
+                let len = r.u32()? as usize;
+                let data = r.take(len)?.to_vec();
+                FsNode::File(File {
+                    inode: Inode { uid, permissions, node_type: NodeType::File },
+                    data,
+                })
+            }
+            TAG_DIR => {
+                let entries = read_entries(r)?;
+                FsNode::Directory(Directory {
+                    inode: Inode { uid, permissions, node_type: NodeType::Directory },
+                    entries,
+                })
+            }
+            _ => return Err(FsError::CorruptImage),
+        };
+
+        entries.insert(name, node);
+    }
+    Ok(entries)
+}
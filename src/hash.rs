@@ -0,0 +1,104 @@
+// hash.rs - minimal no_std password hashing primitives
+// Not a cryptographically vetted KDF: a fast FNV-1a mixing core, iterated with a
+// salt to add a configurable work factor. Good enough to stop storing plaintext
+// until a real RNG/KDF crate is pulled in.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Iteration count applied on top of the initial mix. ~4096 rounds keeps
+/// login latency negligible while making offline guessing noticeably slower.
+pub const DEFAULT_ITERATIONS: u32 = 4096;
+pub const SALT_LEN: usize = 16;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a_fold(mut h: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        h ^= b as u64;
+        h = h.wrapping_mul(FNV_PRIME);
+    }
+    h
+}
+
+/// Core mixing function: `mix(salt || data)`.
+fn mix(salt: &[u8], data: &[u8]) -> u64 {
+    let h = fnv1a_fold(FNV_OFFSET_BASIS, salt);
+    fnv1a_fold(h, data)
+}
+
+/// Computes `h = mix(salt || password)`, then re-feeds `h = mix(salt || h)`
+/// `iterations` times to add work factor.
+pub fn hash_password(password: &[u8], salt: &[u8], iterations: u32) -> u64 {
+    let mut h = mix(salt, password);
+    for _ in 0..iterations {
+        h = mix(salt, &h.to_le_bytes());
+    }
+    h
+}
+
+/// Reads the TSC as a cheap entropy source; there's no RNG driver yet.
+pub fn rdtsc() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+/// Derives `len` salt bytes from the TSC via a small splitmix-style expander.
+pub fn random_salt(len: usize) -> Vec<u8> {
+    let mut state = rdtsc() ^ 0x9E3779B97F4A7C15;
+    let mut out = Vec::with_capacity(len);
+    for _ in 0..len {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        out.push((z >> 56) as u8);
+    }
+    out
+}
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        s.push(HEX_CHARS[(b >> 4) as usize] as char);
+        s.push(HEX_CHARS[(b & 0x0f) as usize] as char);
+    }
+    s
+}
+
+fn hex_val(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => 0,
+    }
+}
+
+pub fn from_hex(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        out.push((hex_val(bytes[i]) << 4) | hex_val(bytes[i + 1]));
+        i += 2;
+    }
+    out
+}
+
+/// Constant-time comparison: slices of different lengths are rejected up
+/// front; equal-length slices are XOR-accumulated over every byte with no
+/// early return, so timing doesn't leak how much of the digest matched.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for i in 0..a.len() {
+        diff |= a[i] ^ b[i];
+    }
+    diff == 0
+}
@@ -34,6 +34,17 @@ lazy_static! {
         idt.double_fault.set_handler_fn(double_fault_handler);
         idt[InterruptIndex::Timer.as_usize()].set_handler_fn(timer_interrupt_handler);
         idt[InterruptIndex::Keyboard.as_usize()].set_handler_fn(keyboard_interrupt_handler);
+
+        // Ring 3 syscall gate. `int80_entry` is a hand-written trampoline
+        // (see `syscall.rs`), not a typed `extern "x86-interrupt" fn", so we
+        // install it via the raw address rather than `set_handler_fn`.
+        // DPL=3 is what lets userland actually execute `int 0x80`.
+        unsafe {
+            idt[0x80]
+                .set_handler_addr(x86_64::VirtAddr::new(crate::syscall::int80_entry as u64))
+                .set_privilege_level(x86_64::PrivilegeLevel::Ring3);
+        }
+
         idt
     };
 }
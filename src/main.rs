@@ -24,6 +24,9 @@ mod allocator;
 mod fs; // Important: link the new file system
 mod shell; // Important: link the new shell
 mod auth;// Important: link the new authentication module
+mod hash; // Password hashing primitives used by auth
+mod syscall; // Ring 3 syscall dispatch table
+mod sink; // Output sink abstraction for redirection/pipes in the shell
 pub mod task;
 pub mod executor;
 
@@ -69,7 +72,10 @@ fn kernel_main(boot_info: &'static BootInfo) -> ! {
     executor.spawn(Task::new(shell::run_shell()));
     
     // 2. On garde l'horloge
-    executor.spawn(Task::new(clock_task()));   
+    executor.spawn(Task::new(clock_task()));
+
+    // On branche l'entrée série sur la même file que le clavier PS/2
+    executor.spawn(Task::new(serial::input_task()));
 
     // 3. On peut garder les autres tâches de fond
     executor.spawn(Task::new(example_task())); 
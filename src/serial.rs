@@ -1,6 +1,7 @@
 use uart_16550::SerialPort;
 use spin::Mutex;
 use lazy_static::lazy_static;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 lazy_static! {
     pub static ref SERIAL1: Mutex<SerialPort> = {
@@ -64,4 +65,81 @@ pub fn read_line() -> alloc::string::String {
         }
     }
     s
+}
+
+/// Set once any byte arrives over serial, so `vga_buffer::_print` starts
+/// mirroring output to `SERIAL1` too — a pure-VGA boot stays quiet on the
+/// wire until something actually drives the shell from that side.
+static CONSOLE_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn console_active() -> bool {
+    CONSOLE_ACTIVE.load(Ordering::Relaxed)
+}
+
+const LINE_STATUS_OFFSET: u16 = 5;
+const DATA_READY: u8 = 1;
+
+/// Non-blocking line-status-register poll. Unlike `read_byte` (which spins
+/// on `SerialPort::receive` until a byte shows up) this returns immediately,
+/// so it's safe to call once per tick from a cooperatively-scheduled task.
+fn try_read_byte() -> Option<u8> {
+    use x86_64::instructions::port::Port;
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let mut lsr: Port<u8> = Port::new(0x3F8 + LINE_STATUS_OFFSET);
+        if unsafe { lsr.read() } & DATA_READY != 0 {
+            Some(SERIAL1.lock().receive())
+        } else {
+            None
+        }
+    })
+}
+
+/// Background task that feeds the serial port into the same `KEY_QUEUE`
+/// the PS/2 keyboard driver pushes into, so `run_shell`/`read_line` drive
+/// identically from either input source. This is what lets JC-OS be
+/// scripted headless under `qemu -serial stdio`.
+pub async fn input_task() {
+    use crate::drivers::keyboard::KEY_QUEUE;
+    use pc_keyboard::DecodedKey;
+
+    // Collapses a CRLF pair from a real terminal into a single '\n',
+    // matching the line-ending tolerance `read_line` already has.
+    let mut last_was_cr = false;
+
+    loop {
+        if let Some(byte) = try_read_byte() {
+            CONSOLE_ACTIVE.store(true, Ordering::Relaxed);
+
+            let decoded = match byte {
+                0x0D => {
+                    last_was_cr = true;
+                    Some('\n')
+                }
+                0x0A => {
+                    let duplicate = last_was_cr;
+                    last_was_cr = false;
+                    if duplicate { None } else { Some('\n') }
+                }
+                0x08 | 0x7F => {
+                    last_was_cr = false;
+                    Some('\u{7f}')
+                }
+                0x20..=0x7E => {
+                    last_was_cr = false;
+                    Some(byte as char)
+                }
+                _ => {
+                    last_was_cr = false;
+                    None
+                }
+            };
+
+            if let Some(ch) = decoded {
+                if KEY_QUEUE.push(DecodedKey::Unicode(ch)).is_err() {
+                    serial_println!("[SERIAL] WARNING: key queue full, dropping input");
+                }
+            }
+        }
+        crate::task::yield_now().await;
+    }
 }
\ No newline at end of file
@@ -1,16 +1,46 @@
-use crate::{print, println, vga_buffer};
+use crate::{print, println, sink_println, vga_buffer};
+use crate::vga_buffer::Style;
+use crate::sink::{OutputSink, VgaSink};
 use alloc::string::{String, ToString}; // On garde les deux finalement !
+use alloc::vec::Vec;
 use pc_keyboard::{DecodedKey, KeyCode};
 use crate::drivers::keyboard::KEY_QUEUE;
-use crate::fs::NodeType; 
+use crate::fs::NodeType;
 use alloc::format;
 
+/// How many past command lines `run_shell` keeps around for Up/Down recall.
+/// Oldest entries are dropped once the bound is hit.
+const HISTORY_CAPACITY: usize = 32;
+
+/// Converts a char offset within `s` into the byte offset `String::insert`/
+/// `String::remove` need, since `command_buffer` is edited mid-line and not
+/// just appended to.
+fn byte_offset(s: &str, char_idx: usize) -> usize {
+    s.char_indices().nth(char_idx).map(|(i, _)| i).unwrap_or(s.len())
+}
+
+/// Finds the completion candidate for the word under the cursor: the single
+/// entry in the current directory whose name starts with that word, if
+/// there's exactly one match.
+fn complete_word(word: &str) -> Option<String> {
+    if word.is_empty() {
+        return None;
+    }
+    let fs = crate::fs::FS.lock();
+    let mut matches = fs.ls().into_iter().filter(|(name, _)| name.starts_with(word));
+    let first = matches.next()?;
+    if matches.next().is_some() {
+        return None;
+    }
+    Some(first.0)
+}
+
 fn print_prompt() {
     let auth = crate::auth::AUTH.lock();
     let fs = crate::fs::FS.lock();
-    
+
     let username = auth.get_current_username();
-    
+
     // Construction du chemin CWD (Current Working Directory)
     let path = if fs.cwd.is_empty() {
         "/".to_string()
@@ -19,7 +49,11 @@ fn print_prompt() {
     };
 
     // Prompt style Linux : andre@jc-os:/home$
-    print!("{}@jc-os:{}$ ", username, path);
+    print!("{}{}@jc-os:{}{}$ ", Style::foreground("lightgreen"), username, path, Style::reset());
+
+    // Line editing (Home, redraw on history recall, ...) must never walk
+    // back over the prompt we just printed.
+    vga_buffer::mark_line_start();
 }
 
 pub async fn run_shell() {
@@ -27,6 +61,17 @@ pub async fn run_shell() {
     println!(" JC-OS - BARE METAL KERNEL v0.4 - RUST EDITION ");
 
     let mut command_buffer = String::with_capacity(256);
+    // Char offset of the insertion point within `command_buffer` — not
+    // always at the end once Left/Right/Home/End are in play.
+    let mut cursor: usize = 0;
+
+    // Up/Down recall: `history` holds completed lines, oldest first;
+    // `history_index` is `Some(i)` while walking it, and `draft` stashes
+    // whatever was being typed before the first Up so Down can get back to
+    // it once you walk past the newest entry.
+    let mut history: Vec<String> = Vec::new();
+    let mut history_index: Option<usize> = None;
+    let mut draft = String::new();
 
     loop { // BOUCLE PRINCIPALE
         // 1. Vérification : est-on connecté ?
@@ -40,19 +85,25 @@ pub async fn run_shell() {
             println!("\n--- LOGIN REQUIRED ---");
             print!("Username: ");
             read_line(&mut user, false).await;
-            
+
             print!("Password: ");
-            read_line(&mut pass, true).await; 
+            read_line(&mut pass, true).await;
 
             if crate::auth::AUTH.lock().login(user.trim(), pass.trim()) {
                 println!("\nWelcome back, {}!", user.trim());
                 command_buffer.clear();
+                cursor = 0;
+                // Don't let the next user recall the previous session's
+                // commands with Up-arrow.
+                history.clear();
+                history_index = None;
+                draft.clear();
                 print_prompt();
             } else {
-                println!("\n[ERROR] Invalid credentials.");
+                println!("\n{}[ERROR]{} Invalid credentials.", Style::foreground("red"), Style::reset());
             }
             // On retourne au début du loop pour vérifier à nouveau is_logged_in
-            continue; 
+            continue;
         }
 
         // 2. PHASE DE COMMANDES (Si connecté)
@@ -63,8 +114,17 @@ pub async fn run_shell() {
                         '\n' | '\r' => {
                             println!("");
                             interpret_command(&command_buffer);
+                            if !command_buffer.trim().is_empty() {
+                                if history.len() >= HISTORY_CAPACITY {
+                                    history.remove(0);
+                                }
+                                history.push(command_buffer.clone());
+                            }
                             command_buffer.clear();
-                            
+                            cursor = 0;
+                            history_index = None;
+                            draft.clear();
+
                             // Si la commande était "logout", on ne print pas de prompt
                             if crate::auth::AUTH.lock().current_user.is_some() {
                                 print_prompt();
@@ -72,15 +132,31 @@ pub async fn run_shell() {
                         }
                         // Support du Backspace en mode Unicode (0x08 ou 0x7F)
                         '\u{8}' | '\u{7f}' => {
-                            if !command_buffer.is_empty() {
-                                command_buffer.pop();
-                                vga_buffer::backspace();
+                            if cursor > 0 {
+                                command_buffer.remove(byte_offset(&command_buffer, cursor - 1));
+                                cursor -= 1;
+                                vga_buffer::delete_before_cursor();
+                            }
+                        }
+                        // Tab-completes the word under the cursor against
+                        // the current directory's entries.
+                        '\t' => {
+                            let cursor_byte = byte_offset(&command_buffer, cursor);
+                            let word_start_byte = command_buffer[..cursor_byte]
+                                .rfind(' ').map(|i| i + 1).unwrap_or(0);
+                            let word = &command_buffer[word_start_byte..cursor_byte];
+                            if let Some(completed) = complete_word(word) {
+                                let word_start_char = command_buffer[..word_start_byte].chars().count();
+                                command_buffer.replace_range(word_start_byte..cursor_byte, &completed);
+                                cursor = word_start_char + completed.chars().count();
+                                vga_buffer::redraw_line(&command_buffer);
                             }
                         }
                         // Accepte tous les caractères imprimables
                         c if c >= ' ' => {
-                            command_buffer.push(c);
-                            print!("{}", c);
+                            command_buffer.insert(byte_offset(&command_buffer, cursor), c);
+                            cursor += 1;
+                            vga_buffer::insert_at_cursor(c);
                         }
                         _ => {}
                     }
@@ -88,17 +164,74 @@ pub async fn run_shell() {
                 DecodedKey::RawKey(code) => {
                     match code {
                         KeyCode::Backspace => {
-                            if !command_buffer.is_empty() {
-                                command_buffer.pop();
-                                vga_buffer::backspace();
+                            if cursor > 0 {
+                                command_buffer.remove(byte_offset(&command_buffer, cursor - 1));
+                                cursor -= 1;
+                                vga_buffer::delete_before_cursor();
+                            }
+                        }
+                        KeyCode::Delete => {
+                            if cursor < command_buffer.chars().count() {
+                                command_buffer.remove(byte_offset(&command_buffer, cursor));
+                                vga_buffer::delete_at_cursor();
+                            }
+                        }
+                        KeyCode::ArrowLeft => {
+                            if cursor > 0 {
+                                cursor -= 1;
+                                vga_buffer::cursor_left();
+                            }
+                        }
+                        KeyCode::ArrowRight => {
+                            if cursor < command_buffer.chars().count() {
+                                cursor += 1;
+                                vga_buffer::cursor_right();
+                            }
+                        }
+                        KeyCode::Home => {
+                            cursor = 0;
+                            vga_buffer::cursor_home();
+                        }
+                        KeyCode::End => {
+                            cursor = command_buffer.chars().count();
+                            vga_buffer::cursor_end();
+                        }
+                        KeyCode::ArrowUp => {
+                            if !history.is_empty() {
+                                let next = match history_index {
+                                    None => {
+                                        draft = command_buffer.clone();
+                                        history.len() - 1
+                                    }
+                                    Some(i) => i.saturating_sub(1),
+                                };
+                                history_index = Some(next);
+                                command_buffer = history[next].clone();
+                                cursor = command_buffer.chars().count();
+                                vga_buffer::redraw_line(&command_buffer);
+                            }
+                        }
+                        KeyCode::ArrowDown => {
+                            if let Some(i) = history_index {
+                                if i + 1 < history.len() {
+                                    history_index = Some(i + 1);
+                                    command_buffer = history[i + 1].clone();
+                                } else {
+                                    history_index = None;
+                                    command_buffer = draft.clone();
+                                }
+                                cursor = command_buffer.chars().count();
+                                vga_buffer::redraw_line(&command_buffer);
                             }
                         }
                         KeyCode::Escape => {
                             command_buffer.clear();
+                            cursor = 0;
+                            history_index = None;
                             vga_buffer::clear_screen();
                             print_prompt();
                         }
-                        _ => {} 
+                        _ => {}
                     }
                 }
             }
@@ -149,6 +282,80 @@ pub fn interpret_command(command: &str) {
     let command = command.trim();
     if command.is_empty() { return; }
 
+    // Redirection binds the whole pipeline (`a | b > file` captures `b`'s
+    // output), so it's split off before `|` gets a chance to.
+    if let Some((pipeline, file, append)) = split_redirect(command) {
+        let mut captured = String::new();
+        run_pipeline(pipeline, &mut captured);
+
+        let uid = crate::auth::AUTH.lock().get_current_uid();
+        let mut fs = crate::fs::FS.lock();
+        let result = if append {
+            let existing = fs.read_file(file, uid).unwrap_or_default();
+            fs.write_file(file, &(existing + &captured), uid)
+        } else {
+            fs.write_file(file, &captured, uid)
+        };
+        if let Err(e) = result {
+            println!("Error: {}", e.as_str());
+        }
+        return;
+    }
+
+    let mut sink = VgaSink;
+    run_pipeline(command, &mut sink);
+}
+
+/// Splits `cmd > file` / `cmd >> file` off of a command line, returning the
+/// remaining pipeline, the target file, and whether to append.
+fn split_redirect(command: &str) -> Option<(&str, &str, bool)> {
+    if let Some(pos) = command.find(">>") {
+        let file = command[pos + 2..].trim();
+        if file.is_empty() { return None; }
+        return Some((command[..pos].trim(), file, true));
+    }
+    if let Some(pos) = command.find('>') {
+        let file = command[pos + 1..].trim();
+        if file.is_empty() { return None; }
+        return Some((command[..pos].trim(), file, false));
+    }
+    None
+}
+
+/// Runs each `|`-separated stage through `run_command`, capturing every
+/// non-final stage's output in memory and appending it to the next stage's
+/// own arguments — so `read config | note copy` writes `config`'s contents
+/// into `copy`. The last stage writes straight to `sink`.
+///
+/// There's no stdin sink, so this only reads naturally for commands whose
+/// last argument is exactly "the rest of the text", like `note`/`edit`.
+/// Piping into a command that doesn't expect trailing free-form text (e.g.
+/// `look | read`, `stats | whoami`) just appends the captured output as a
+/// garbled extra argument rather than feeding it in as input.
+fn run_pipeline(command: &str, sink: &mut dyn OutputSink) {
+    let mut stages = command.split('|').map(str::trim).filter(|s| !s.is_empty()).peekable();
+    let mut carried = String::new();
+
+    while let Some(stage) = stages.next() {
+        let stage_line = if carried.is_empty() {
+            String::from(stage)
+        } else {
+            format!("{} {}", stage, carried.trim())
+        };
+
+        if stages.peek().is_some() {
+            carried.clear();
+            run_command(&stage_line, &mut carried);
+        } else {
+            run_command(&stage_line, sink);
+        }
+    }
+}
+
+/// The actual command dispatch, parameterized over where its output goes.
+/// `interpret_command` is the normal entry point (straight to VGA); redirect
+/// and pipe handling in `run_pipeline` route through a `String` sink instead.
+fn run_command(command: &str, sink: &mut dyn OutputSink) {
     let mut parts = command.splitn(2, ' ');
     let cmd = parts.next().unwrap_or("");
     let args = parts.next().unwrap_or("");
@@ -158,8 +365,13 @@ pub fn interpret_command(command: &str) {
 
     match cmd {
         "help" => {
-            println!("Commands: help, info, whoami, clear, stats, neofetch");
-            println!("FS: look, open <dir>, room <name>, where, note <file> <text>, read <file>, drop <file>");
+            sink_println!(sink, "Commands: help, info, whoami, clear, stats, neofetch");
+            sink_println!(sink, "FS: look, open <dir>, room <name>, where, note <file> <text>, read <file>, drop <file>");
+            sink_println!(sink, "FS perms: chmod <octal> <file>, chown <user> <file>");
+            sink_println!(sink, "Trash: trash, restore <id>, empty-trash");
+            sink_println!(sink, "Admin: snapshot, restore-snapshot (in-memory FS snapshot round-trip)");
+            sink_println!(sink, "Appearance: theme <file> (remaps palette names from a name,color CSV)");
+            sink_println!(sink, "Redirection/pipes: cmd > file, cmd >> file, a | b");
         },
 
        "useradd" => {
@@ -170,38 +382,40 @@ pub fn interpret_command(command: &str) {
     };
 
     if !is_admin {
-        println!("[PERMISSION DENIED] Only administrators can add users.");
+        sink_println!(sink, "[PERMISSION DENIED] Only administrators can add users.");
     } else {
         let mut arg_parts = args.splitn(2, ' ');
         let new_username = arg_parts.next().unwrap_or("");
         let new_password = arg_parts.next().unwrap_or("").trim();
 
         if new_username.is_empty() || new_password.is_empty() {
-            println!("Usage: useradd <username> <password>");
+            sink_println!(sink, "Usage: useradd <username> <password>");
         } else {
             // 1. Ajouter l'utilisateur dans le système d'authentification
             let mut auth = crate::auth::AUTH.lock();
             match auth.add_user(new_username, new_password) {
                 Ok(new_uid) => {
-                    println!("[AUTH] User '{}' created with UID {}.", new_username, new_uid);
-                    
-                    // 2. Créer automatiquement son dossier Home
+                    sink_println!(sink, "[AUTH] User '{}' created with UID {}.", new_username, new_uid);
+                    // The FS permission checks take AUTH themselves.
+                    drop(auth);
+
+                    // 2. Créer automatiquement son dossier Home (created with admin rights, then handed to the new user)
                     let mut fs = crate::fs::FS.lock();
-                    
+
                     // On s'assure que /home existe
-                    let _ = fs.room("home", 0); 
-                    
+                    let _ = fs.mkdir("home", 0, false);
+
                     let old_cwd = fs.cwd.clone();
-                    if fs.open("/home").is_ok() {
-                        if let Err(e) = fs.room(new_username, new_uid) {
-                            println!("[FS ERROR] Could not create home directory: {}", e);
+                    if fs.cd("/home", 0).is_ok() {
+                        if let Err(e) = fs.mkdir(new_username, 0, false).and_then(|_| fs.chown(new_username, new_uid, 0)) {
+                            sink_println!(sink, "{}[FS ERROR]{} Could not create home directory: {}", Style::foreground("red"), Style::reset(), e.as_str());
                         } else {
-                            println!("[FS] Home directory /home/{} created.", new_username);
+                            sink_println!(sink, "[FS] Home directory /home/{} created.", new_username);
                         }
                     }
                     fs.cwd = old_cwd;
                 },
-                Err(e) => println!("[ERROR] {}", e),
+                Err(e) => sink_println!(sink, "{}[ERROR]{} {}", Style::foreground("red"), Style::reset(), e),
             }
         }
     }
@@ -215,39 +429,69 @@ pub fn interpret_command(command: &str) {
     };
 
     if !is_admin {
-        println!("[PERMISSION DENIED] Only administrators can delete users.");
+        sink_println!(sink, "[PERMISSION DENIED] Only administrators can delete users.");
     } else {
         let username_to_del = args.trim();
         if username_to_del.is_empty() {
-            println!("Usage: userdel <username>");
+            sink_println!(sink, "Usage: userdel <username>");
         } else {
             // 1. Supprimer du système d'authentification
             let mut auth = crate::auth::AUTH.lock();
             match auth.delete_user(username_to_del) {
                 Ok(_) => {
-                    println!("[AUTH] User '{}' deleted.", username_to_del);
-                    
+                    sink_println!(sink, "[AUTH] User '{}' deleted.", username_to_del);
+                    // The FS permission checks take AUTH themselves.
+                    drop(auth);
+
                     // 2. Supprimer son home directory
                     let mut fs = crate::fs::FS.lock();
                     let old_cwd = fs.cwd.clone();
-                    if fs.open("/home").is_ok() {
-                        if fs.remove_file(username_to_del) {
-                            println!("[FS] Home directory /home/{} removed.", username_to_del);
+                    if fs.cd("/home", 0).is_ok() {
+                        if fs.remove_dir(username_to_del, 0).is_ok() {
+                            sink_println!(sink, "[FS] Home directory /home/{} removed.", username_to_del);
                         }
                     }
                     fs.cwd = old_cwd;
                 },
-                Err(e) => println!("[ERROR] {}", e),
+                Err(e) => sink_println!(sink, "{}[ERROR]{} {}", Style::foreground("red"), Style::reset(), e),
             }
         }
     }
 },
 
+        "snapshot" => {
+            let is_admin = {
+                let auth = crate::auth::AUTH.lock();
+                auth.current_user.as_ref().map(|u| u.role == crate::auth::Role::Admin).unwrap_or(false)
+            };
+            if !is_admin {
+                sink_println!(sink, "[PERMISSION DENIED] Only administrators can snapshot the filesystem.");
+            } else {
+                crate::fs::save_snapshot();
+                sink_println!(sink, "Filesystem snapshot saved.");
+            }
+        },
+
+        "restore-snapshot" => {
+            let is_admin = {
+                let auth = crate::auth::AUTH.lock();
+                auth.current_user.as_ref().map(|u| u.role == crate::auth::Role::Admin).unwrap_or(false)
+            };
+            if !is_admin {
+                sink_println!(sink, "[PERMISSION DENIED] Only administrators can restore a filesystem snapshot.");
+            } else {
+                match crate::fs::load_snapshot() {
+                    Ok(_) => sink_println!(sink, "Filesystem restored from snapshot."),
+                    Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+                }
+            }
+        },
+
         "logout" => {
             crate::auth::AUTH.lock().logout();
-            println!("Logged out.");
+            sink_println!(sink, "Logged out.");
             // Note: Le loop principal du shell va nous redemander le login au prochain tour
-            return; 
+            return;
         },
 
         "edit" => {
@@ -256,55 +500,55 @@ pub fn interpret_command(command: &str) {
         let new_content = arg_parts.next().unwrap_or("");
 
     if file_name.is_empty() {
-        println!("Usage: edit <filename> <text>");
+        sink_println!(sink, "Usage: edit <filename> <text>");
     } else {
         let current_uid = crate::auth::AUTH.lock().get_current_uid();
         // On réutilise write_file qui écrase le contenu existant
         let mut fs = crate::fs::FS.lock();
         match fs.write_file(file_name, new_content, current_uid) {
-            Ok(_) => println!("File '{}' updated.", file_name),
-            Err(e) => println!("[ERROR] Could not edit file: {}", e),
+            Ok(_) => sink_println!(sink, "File '{}' updated.", file_name),
+            Err(e) => sink_println!(sink, "{}[ERROR]{} Could not edit file: {}", Style::foreground("red"), Style::reset(), e.as_str()),
         }
     }
 },
 
         "where" => {
             let fs = crate::fs::FS.lock();
-            println!("/{}", fs.cwd.join("/"));
+            sink_println!(sink, "/{}", fs.cwd.join("/"));
         },
 
         "look" => {
             let fs = crate::fs::FS.lock();
-            let entries = fs.look();
+            let entries = fs.ls();
             if entries.is_empty() {
-                println!("Empty directory.");
+                sink_println!(sink, "Empty directory.");
             } else {
                 for (name, node_type) in entries {
                     // Utilisation directe du type importé
                     match node_type {
-                        NodeType::Directory => println!("{}/", name),
-                        NodeType::File => println!("{}", name),
+                        NodeType::Directory => sink_println!(sink, "{}{}/{}", Style::foreground("blue"), name, Style::reset()),
+                        NodeType::File => sink_println!(sink, "{}", name),
                     }
                 }
             }
         },
         "open" => {
             if args.is_empty() {
-                println!("Usage: open <directory>");
+                sink_println!(sink, "Usage: open <directory>");
             } else {
-                if let Err(e) = crate::fs::FS.lock().open(args) {
-                    println!("Error: {}", e);
+                if let Err(e) = crate::fs::FS.lock().cd(args, current_uid) {
+                    sink_println!(sink, "Error: {}", e.as_str());
                 }
             }
         },
 
         "room" => {
             if args.is_empty() {
-                println!("Usage: room <name>");
+                sink_println!(sink, "Usage: room <name>");
             } else {
                 // On passe bien 2 arguments : le nom et l'UID
-                if let Err(e) = crate::fs::FS.lock().room(args, current_uid) {
-                    println!("Error: {}", e);
+                if let Err(e) = crate::fs::FS.lock().mkdir(args, current_uid, false) {
+                    sink_println!(sink, "Error: {}", e.as_str());
                 }
             }
         },
@@ -314,13 +558,13 @@ pub fn interpret_command(command: &str) {
             let name = arg_parts.next().unwrap_or("");
             let content = arg_parts.next().unwrap_or("");
             if name.is_empty() {
-                println!("Usage: note <filename> <content>");
+                sink_println!(sink, "Usage: note <filename> <content>");
             } else {
                 // On utilise le Result et on passe l'UID
                 if let Err(e) = crate::fs::FS.lock().write_file(name, content, current_uid) {
-                    println!("Error: {}", e);
+                    sink_println!(sink, "Error: {}", e.as_str());
                 } else {
-                    println!("File '{}' created.", name);
+                    sink_println!(sink, "File '{}' created.", name);
                 }
             }
         },
@@ -328,45 +572,127 @@ pub fn interpret_command(command: &str) {
         "drop" => {
             let filename = args.trim();
             if filename.is_empty() {
-                println!("Usage: drop <filename>");
+                sink_println!(sink, "Usage: drop <filename>");
             } else {
                 let mut fs = crate::fs::FS.lock();
-                if fs.remove_file(filename) {
-                    println!("File '{}' removed.", filename);
-                } else {
-                    println!("Error: Could not find or remove '{}'.", filename);
+                match fs.remove_file(filename, current_uid) {
+                    Ok(_) => sink_println!(sink, "File '{}' removed.", filename),
+                    Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
                 }
             }
         },
         "read" => {
             let filename = args.trim();
-            // Attention : read_file dans le FS pro doit être mis à jour pour chercher dans le CWD
-            // Pour l'instant, on utilise la logique simplifiée
-            if let Some(content) = crate::fs::FS.lock().read_file(filename) {
-                println!("{}", content);
+            match crate::fs::FS.lock().read_file(filename, current_uid) {
+                Ok(content) => sink_println!(sink, "{}", content),
+                Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+            }
+        },
+
+        "trash" => {
+            let entries = crate::fs::FS.lock().list_trash();
+            if entries.is_empty() {
+                sink_println!(sink, "Trash is empty.");
+            } else {
+                for (seq, path, node_type) in entries {
+                    match node_type {
+                        NodeType::Directory => sink_println!(sink, "[{}] {}/ ", seq, path),
+                        NodeType::File => sink_println!(sink, "[{}] {}", seq, path),
+                    }
+                }
+            }
+        },
+
+        "restore" => {
+            let seq_str = args.trim();
+            match seq_str.parse::<u64>() {
+                Ok(seq) => match crate::fs::FS.lock().restore(seq) {
+                    Ok(_) => sink_println!(sink, "Restored trash entry {}.", seq),
+                    Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+                },
+                Err(_) => sink_println!(sink, "Usage: restore <id>"),
+            }
+        },
+
+        "empty-trash" => {
+            crate::fs::FS.lock().empty_trash();
+            sink_println!(sink, "Trash emptied.");
+        },
+
+        "chmod" => {
+            let mut arg_parts = args.splitn(2, ' ');
+            let mode_str = arg_parts.next().unwrap_or("");
+            let filename = arg_parts.next().unwrap_or("").trim();
+            if mode_str.is_empty() || filename.is_empty() {
+                sink_println!(sink, "Usage: chmod <octal> <file>");
+            } else {
+                match u16::from_str_radix(mode_str, 8) {
+                    Ok(mode) => match crate::fs::FS.lock().chmod(filename, mode, current_uid) {
+                        Ok(_) => sink_println!(sink, "Permissions of '{}' set to {}.", filename, mode_str),
+                        Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+                    },
+                    Err(_) => sink_println!(sink, "Usage: chmod <octal> <file>"),
+                }
+            }
+        },
+
+        "chown" => {
+            let mut arg_parts = args.splitn(2, ' ');
+            let new_owner = arg_parts.next().unwrap_or("");
+            let filename = arg_parts.next().unwrap_or("").trim();
+            if new_owner.is_empty() || filename.is_empty() {
+                sink_println!(sink, "Usage: chown <user> <file>");
             } else {
-                println!("Error: File '{}' not found.", filename);
+                let new_uid = {
+                    let auth = crate::auth::AUTH.lock();
+                    auth.users.iter().find(|u| u.username == new_owner).map(|u| u.uid)
+                };
+                match new_uid {
+                    Some(new_uid) => match crate::fs::FS.lock().chown(filename, new_uid, current_uid) {
+                        Ok(_) => sink_println!(sink, "Owner of '{}' set to {}.", filename, new_owner),
+                        Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+                    },
+                    None => sink_println!(sink, "Error: no such user '{}'.", new_owner),
+                }
             }
         },
 
         "whoami" => {
-            println!("{}", crate::auth::AUTH.lock().get_current_username());
+            sink_println!(sink, "{}", crate::auth::AUTH.lock().get_current_username());
         },
 
         "clear" => vga_buffer::clear_screen(),
 
         "stats" => {
             let (file_count, total_bytes) = crate::fs::FS.lock().get_stats();
-            println!("Files/Folders : {}", file_count);
-            println!("Used Space    : {} bytes", total_bytes);
+            sink_println!(sink, "Files/Folders : {}", file_count);
+            sink_println!(sink, "Used Space    : {} bytes", total_bytes);
         },
 
         "neofetch" => {
-            println!("   _/_/    JC-OS v0.4 Pro");
-            println!("  _/       User: {}", crate::auth::AUTH.lock().get_current_username());
-            println!(" _/_/_/    FS  : Hierarchical RAMFS");
+            let art = Style::foreground("lightcyan");
+            let reset = Style::reset();
+            sink_println!(sink, "{}   _/_/    {}JC-OS v0.4 Pro", art, reset);
+            sink_println!(sink, "{}  _/       {}User: {}", art, reset, crate::auth::AUTH.lock().get_current_username());
+            sink_println!(sink, "{} _/_/_/    {}FS  : Hierarchical RAMFS", art, reset);
+        },
+
+        "theme" => {
+            let filename = args.trim();
+            if filename.is_empty() {
+                sink_println!(sink, "Usage: theme <file>");
+            } else {
+                let current_uid = crate::auth::AUTH.lock().get_current_uid();
+                match crate::fs::FS.lock().read_file(filename, current_uid) {
+                    Ok(csv) => {
+                        let applied = vga_buffer::palette::from_csv(&csv);
+                        sink_println!(sink, "Palette updated: {} name(s) remapped.", applied);
+                    }
+                    Err(e) => sink_println!(sink, "Error: {}", e.as_str()),
+                }
+            }
         },
 
-        _ => println!("Unknown command: {}", cmd),
+        _ => sink_println!(sink, "Unknown command: {}", cmd),
     }
 }
\ No newline at end of file
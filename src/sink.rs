@@ -0,0 +1,50 @@
+// sink.rs - Abstract output destination for shell commands.
+//
+// Commands used to call `println!`/`print!` straight at the VGA buffer, which
+// made redirection (`>`, `>>`) and pipes (`|`) impossible: there was nowhere
+// else for the output to go. `OutputSink` gives commands one indirection to
+// write through instead — `VgaSink` for the normal interactive path, `String`
+// for a pipeline stage or a file capture — and is the same indirection a
+// syscall `write(fd, ...)` path would need once file descriptors exist.
+
+use alloc::string::String;
+use core::fmt;
+
+pub trait OutputSink {
+    fn write_fmt(&mut self, args: fmt::Arguments);
+}
+
+/// Writes straight through to the interactive VGA console (and, if a serial
+/// console session is active, `SERIAL1` too — see `vga_buffer::_print`).
+pub struct VgaSink;
+
+impl OutputSink for VgaSink {
+    fn write_fmt(&mut self, args: fmt::Arguments) {
+        crate::vga_buffer::_print(args);
+    }
+}
+
+/// Captures output in memory instead of printing it: the sink a redirected
+/// file write or a pipeline stage reads back from.
+impl OutputSink for String {
+    fn write_fmt(&mut self, args: fmt::Arguments) {
+        use core::fmt::Write;
+        let _ = Write::write_fmt(self, args);
+    }
+}
+
+/// `println!`-alike that writes through an `OutputSink` instead of directly
+/// to the VGA buffer. Reborrows (`&mut *$sink`) rather than moves, since a
+/// single match arm typically writes through the same sink several times.
+#[macro_export]
+macro_rules! sink_println {
+    ($sink:expr) => ($crate::sink::OutputSink::write_fmt(&mut *$sink, format_args!("\n")));
+    ($sink:expr, $($arg:tt)*) => ($crate::sink::OutputSink::write_fmt(&mut *$sink, format_args!("{}\n", format_args!($($arg)*))));
+}
+
+/// `print!`-alike that writes through an `OutputSink` instead of directly
+/// to the VGA buffer.
+#[macro_export]
+macro_rules! sink_print {
+    ($sink:expr, $($arg:tt)*) => ($crate::sink::OutputSink::write_fmt(&mut *$sink, format_args!($($arg)*)));
+}
@@ -0,0 +1,181 @@
+// syscall.rs - Ring 3 entry point and dispatch table.
+//
+// Userland reaches the kernel through `int 0x80`, serviced by a DPL=3 gate
+// installed at IDT vector 0x80 (see `interrupts::init_idt`). Arguments
+// follow the Linux `syscall` register convention used throughout the `nc`
+// crate: the number in rax, up to five arguments in rdi/rsi/rdx/r10/r8, and
+// the result handed back in rax as `-errno` on failure. The `x86-interrupt`
+// ABI can't expose general-purpose registers, so the gate itself is a
+// hand-written trampoline that saves the full frame, reshuffles the
+// syscall-convention registers into the System V call convention `dispatch`
+// expects, calls it, and writes the result back into the saved rax slot
+// before `iretq`.
+
+use crate::{auth, drivers::keyboard::KEY_QUEUE, fs, print, serial_print};
+
+pub const SYS_READ: usize = 0;
+pub const SYS_WRITE: usize = 1;
+pub const SYS_OPEN: usize = 2;
+pub const SYS_CLOSE: usize = 3;
+pub const SYS_GETUID: usize = 102;
+pub const SYS_EXIT: usize = 60;
+
+const ENOSYS: isize = -38;
+const EFAULT: isize = -14;
+const EBADF: isize = -9;
+const EACCES: isize = -13;
+const ENOENT: isize = -2;
+
+/// Conventional user/kernel address split. There's no per-task page table
+/// handle threaded through to syscalls yet, so this is a coarse range check
+/// rather than a real page-table walk — good enough to stop a stray or
+/// hostile pointer from reaching kernel space, not a full mapping audit.
+const USER_SPACE_END: usize = 0x0000_8000_0000_0000;
+
+fn validate_user_ptr(ptr: usize, len: usize) -> bool {
+    if ptr == 0 {
+        return false;
+    }
+    match ptr.checked_add(len) {
+        Some(end) => end <= USER_SPACE_END,
+        None => false,
+    }
+}
+
+/// Linux-style numbered dispatch: `nr` selects the call, `a1..a5` are its
+/// arguments (five is enough for this table), and the return value follows
+/// the `-errno` convention.
+pub extern "C" fn dispatch(nr: usize, a1: usize, a2: usize, a3: usize, a4: usize, a5: usize) -> isize {
+    match nr {
+        SYS_READ => sys_read(a1, a2 as *mut u8, a3),
+        SYS_WRITE => sys_write(a1, a2 as *const u8, a3),
+        SYS_OPEN => sys_open(a1 as *const u8, a2, a3),
+        SYS_CLOSE => sys_close(a1),
+        SYS_GETUID => auth::AUTH.lock().get_current_uid() as isize,
+        SYS_EXIT => sys_exit(a1 as isize),
+        _ => {
+            let _ = (a4, a5);
+            ENOSYS
+        }
+    }
+}
+
+fn sys_read(fd: usize, buf: *mut u8, len: usize) -> isize {
+    if fd != 0 {
+        return EBADF;
+    }
+    if !validate_user_ptr(buf as usize, len) {
+        return EFAULT;
+    }
+
+    let mut written = 0usize;
+    while written < len {
+        match KEY_QUEUE.pop() {
+            Some(pc_keyboard::DecodedKey::Unicode(ch)) if (ch as u32) < 0x80 => {
+                unsafe { buf.add(written).write(ch as u8); }
+                written += 1;
+            }
+            Some(_) => continue, // non-ASCII / raw key, nothing to deliver as a byte
+            None => break,       // no more input buffered right now
+        }
+    }
+    written as isize
+}
+
+fn sys_write(fd: usize, buf: *const u8, len: usize) -> isize {
+    if !validate_user_ptr(buf as usize, len) {
+        return EFAULT;
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(buf, len) };
+    let text = core::str::from_utf8(bytes).unwrap_or("<invalid utf-8>");
+
+    match fd {
+        1 => { print!("{}", text); len as isize }
+        2 => { serial_print!("{}", text); len as isize }
+        _ => EBADF,
+    }
+}
+
+fn sys_open(path_ptr: *const u8, path_len: usize, _flags: usize) -> isize {
+    if !validate_user_ptr(path_ptr as usize, path_len) {
+        return EFAULT;
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(path_ptr, path_len) };
+    let path = match core::str::from_utf8(bytes) {
+        Ok(p) => p,
+        Err(_) => return EFAULT,
+    };
+
+    let uid = auth::AUTH.lock().get_current_uid();
+    match fs::FS.lock().read_file(path, uid) {
+        // There's no per-process fd table yet, so every successful open
+        // hands back the same placeholder descriptor.
+        Ok(_) => 0,
+        Err(fs::FsError::PermissionDenied) => EACCES,
+        Err(_) => ENOENT,
+    }
+}
+
+fn sys_close(_fd: usize) -> isize {
+    0
+}
+
+fn sys_exit(_code: isize) -> isize {
+    // No per-task process table yet: a Ring 3 task exiting just halts this
+    // core. Once userland tasks are real `Task`s in the executor, this
+    // should drop the task instead.
+    loop {
+        x86_64::instructions::hlt();
+    }
+}
+
+core::arch::global_asm!(
+    ".global int80_entry",
+    "int80_entry:",
+    "push rax",
+    "push rbx",
+    "push rcx",
+    "push rdx",
+    "push rsi",
+    "push rdi",
+    "push r8",
+    "push r9",
+    "push r10",
+    "push r11",
+    "push rbp",
+    // Reshuffle the Linux syscall-convention args (nr=rax, rdi, rsi, rdx,
+    // r10, r8) into System V call args for `dispatch` (rdi, rsi, rdx, rcx,
+    // r8, r9). Each move below reads a register that hasn't been
+    // overwritten yet, so the two independent chains (rax->rdi->rsi->rdx->rcx
+    // and r10->r8->r9) can run in any order relative to each other.
+    "mov rcx, rdx",
+    "mov rdx, rsi",
+    "mov rsi, rdi",
+    "mov rdi, rax",
+    "mov r9, r8",
+    "mov r8, r10",
+    "call {dispatch}",
+    // The saved rax slot is 10 pushes below the one we just wrote (rbx..rbp),
+    // i.e. at rsp+80; overwrite it so the popped rax carries dispatch's
+    // return value back to the caller.
+    "mov [rsp + 80], rax",
+    "pop rbp",
+    "pop r11",
+    "pop r10",
+    "pop r9",
+    "pop r8",
+    "pop rdi",
+    "pop rsi",
+    "pop rdx",
+    "pop rcx",
+    "pop rbx",
+    "pop rax",
+    "iretq",
+    dispatch = sym dispatch,
+);
+
+extern "C" {
+    /// Entry point for the `int 0x80` IDT gate; defined by the `global_asm!`
+    /// block above. Registered at vector 0x80 with DPL=3 by `interrupts::init_idt`.
+    pub fn int80_entry();
+}
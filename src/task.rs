@@ -35,11 +35,15 @@ pub struct YieldNow {
 impl Future for YieldNow {
     type Output = ();
 
-    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
         if self.yielded {
             Poll::Ready(())
         } else {
             self.yielded = true;
+            // Nothing else re-wakes a cooperative yield, so we have to wake
+            // ourselves here or the executor never re-polls this task and
+            // the whole system halts in `sleep_if_idle` after one round.
+            cx.waker().wake_by_ref();
             Poll::Pending
         }
     }
@@ -3,6 +3,9 @@ use core::fmt;
 use spin::Mutex;
 use lazy_static::lazy_static;
 use x86_64::instructions::port::Port;
+use alloc::vec::Vec;
+use alloc::string::String;
+use alloc::collections::BTreeMap;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,17 +42,47 @@ struct Buffer {
     chars: [[Volatile<ScreenChar>; BUFFER_WIDTH]; BUFFER_HEIGHT],
 }
 
+/// Default palette used on boot and on SGR reset (`ESC [ 0 m`).
+const DEFAULT_FOREGROUND: Color = Color::LightGreen;
+const DEFAULT_BACKGROUND: Color = Color::Black;
+
+/// Caps how many `;`-separated SGR parameters we'll buffer before giving up
+/// on an overlong sequence and falling back to plain printing.
+const MAX_SGR_PARAMS: usize = 8;
+
+/// Parser state for the `ESC [ ... m` (SGR) escape-sequence state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnsiState {
+    Ground,
+    Escape,
+    Csi,
+}
+
 pub struct Writer {
     pub column_position: usize,
     pub row_position: usize,
     // On mémorise la fin de chaque ligne pour le backspace intelligent
     line_lengths: [usize; BUFFER_HEIGHT],
+    // Column where the editable part of each row starts — after a prompt
+    // like `andre@jc-os:/$ `, home/redraw/insert must never touch the
+    // columns before this.
+    line_starts: [usize; BUFFER_HEIGHT],
     pub color_code: ColorCode,
+    foreground: Color,
+    background: Color,
+    bold: bool,
+    ansi_state: AnsiState,
+    ansi_params: Vec<u16>,
+    ansi_current: Option<u16>,
     buffer: &'static mut Buffer,
 }
 
 impl Writer {
     pub fn write_byte(&mut self, byte: u8) {
+        if self.handle_ansi_byte(byte) {
+            return;
+        }
+
         match byte {
             b'\n' => self.new_line(),
             byte => {
@@ -69,13 +102,106 @@ impl Writer {
                 self.line_lengths[self.row_position] = self.column_position;
             }
         }
-        self.update_cursor(); 
+        self.update_cursor();
+    }
+
+    /// Feeds `byte` through the SGR escape-sequence parser. Returns `true`
+    /// if the byte was consumed by the state machine (and must not be
+    /// drawn to the screen).
+    fn handle_ansi_byte(&mut self, byte: u8) -> bool {
+        match self.ansi_state {
+            AnsiState::Ground => {
+                if byte == 0x1B {
+                    self.ansi_state = AnsiState::Escape;
+                    true
+                } else {
+                    false
+                }
+            }
+            AnsiState::Escape => {
+                self.ansi_state = if byte == b'[' {
+                    self.ansi_params.clear();
+                    self.ansi_current = None;
+                    AnsiState::Csi
+                } else {
+                    // Not a CSI sequence after all: abort gracefully.
+                    AnsiState::Ground
+                };
+                true
+            }
+            AnsiState::Csi => {
+                match byte {
+                    b'0'..=b'9' => {
+                        let digit = (byte - b'0') as u16;
+                        let acc = self.ansi_current.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                        self.ansi_current = Some(acc);
+                        if self.ansi_params.len() >= MAX_SGR_PARAMS {
+                            // Overlong sequence: bail out and resume printing.
+                            self.ansi_state = AnsiState::Ground;
+                        }
+                    }
+                    b';' => {
+                        self.ansi_params.push(self.ansi_current.take().unwrap_or(0));
+                        if self.ansi_params.len() > MAX_SGR_PARAMS {
+                            self.ansi_state = AnsiState::Ground;
+                        }
+                    }
+                    b'm' => {
+                        self.ansi_params.push(self.ansi_current.take().unwrap_or(0));
+                        self.apply_sgr();
+                        self.ansi_state = AnsiState::Ground;
+                    }
+                    _ => {
+                        // Unsupported/malformed final byte: abort gracefully.
+                        self.ansi_state = AnsiState::Ground;
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// Applies the accumulated SGR parameters to the writer's current
+    /// foreground/background/bold state.
+    fn apply_sgr(&mut self) {
+        if self.ansi_params.is_empty() {
+            self.reset_colors();
+            return;
+        }
+
+        for &code in &self.ansi_params {
+            match code {
+                0 => self.reset_colors(),
+                1 => self.bold = true,
+                30..=37 => if let Some(c) = ansi_color(code - 30) { self.foreground = c; },
+                90..=97 => if let Some(c) = ansi_bright_color(code - 90) { self.foreground = c; },
+                40..=47 => if let Some(c) = ansi_color(code - 40) { self.background = c; },
+                100..=107 => if let Some(c) = ansi_bright_color(code - 100) { self.background = c; },
+                _ => {} // unsupported SGR code, ignore
+            }
+        }
+        self.recompute_color_code();
+    }
+
+    fn reset_colors(&mut self) {
+        self.foreground = DEFAULT_FOREGROUND;
+        self.background = DEFAULT_BACKGROUND;
+        self.bold = false;
+        self.recompute_color_code();
+    }
+
+    fn recompute_color_code(&mut self) {
+        let fg = if self.bold { brighten(self.foreground) } else { self.foreground };
+        self.color_code = ColorCode::new(fg, self.background);
     }
 
     pub fn write_string(&mut self, s: &str) {
         for byte in s.bytes() {
             match byte {
-                0x20..=0x7e | b'\n' => self.write_byte(byte),
+                // ESC (0x1B) kicks off an SGR sequence; `write_byte`/
+                // `handle_ansi_byte` consume it and everything through the
+                // final `m` themselves, so it must reach them unfiltered.
+                0x20..=0x7e | b'\n' | 0x1b => self.write_byte(byte),
                 _ => self.write_byte(0xfe),
             }
         }
@@ -84,6 +210,7 @@ impl Writer {
     pub fn new_line(&mut self) {
         if self.row_position < BUFFER_HEIGHT - 1 {
             self.row_position += 1;
+            self.line_starts[self.row_position] = 0;
         } else {
             // Scroll : on décale aussi les longueurs de lignes
             for row in 1..BUFFER_HEIGHT {
@@ -92,9 +219,11 @@ impl Writer {
                     self.buffer.chars[row - 1][col].write(character);
                 }
                 self.line_lengths[row - 1] = self.line_lengths[row];
+                self.line_starts[row - 1] = self.line_starts[row];
             }
             self.clear_row(BUFFER_HEIGHT - 1);
             self.line_lengths[BUFFER_HEIGHT - 1] = 0;
+            self.line_starts[BUFFER_HEIGHT - 1] = 0;
         }
         self.column_position = 0;
         self.update_cursor();
@@ -109,6 +238,7 @@ impl Writer {
             self.buffer.chars[row][col].write(blank);
         }
         self.line_lengths[row] = 0;
+        self.line_starts[row] = 0;
     }
 
     pub fn clear_screen(&mut self) {
@@ -170,6 +300,119 @@ impl Writer {
         self.line_lengths[self.row_position] = self.column_position;
         self.update_cursor();
     }
+
+    /// Records the current column as where the editable line starts on this
+    /// row (e.g. right after a prompt has just been printed), so Home,
+    /// Left, and line redraws know not to walk back over it.
+    pub fn mark_line_start(&mut self) {
+        self.line_starts[self.row_position] = self.column_position;
+    }
+
+    /// Moves the cursor one column left within the current line, for
+    /// readline-style editing. Unlike `backspace`, nothing is deleted.
+    pub fn cursor_left(&mut self) {
+        if self.column_position > self.line_starts[self.row_position] {
+            self.column_position -= 1;
+            self.update_cursor();
+        }
+    }
+
+    /// Moves the cursor one column right, stopping at the end of whatever
+    /// has actually been written on this line.
+    pub fn cursor_right(&mut self) {
+        if self.column_position < self.line_lengths[self.row_position] {
+            self.column_position += 1;
+            self.update_cursor();
+        }
+    }
+
+    /// Jumps to the start of the editable line (Home key) — just past the
+    /// prompt, not column 0.
+    pub fn cursor_home(&mut self) {
+        self.column_position = self.line_starts[self.row_position];
+        self.update_cursor();
+    }
+
+    /// Jumps to the end of the current line's written text (End key).
+    pub fn cursor_end(&mut self) {
+        self.column_position = self.line_lengths[self.row_position];
+        self.update_cursor();
+    }
+
+    /// Inserts `byte` at the cursor, shifting everything after it one
+    /// column right. Lines don't wrap for editing purposes, so this is a
+    /// no-op once the row is full.
+    pub fn insert_at_cursor(&mut self, byte: u8) {
+        let row = self.row_position;
+        let len = self.line_lengths[row];
+        if len >= BUFFER_WIDTH {
+            return;
+        }
+        for col in (self.column_position..len).rev() {
+            let ch = self.buffer.chars[row][col].read();
+            self.buffer.chars[row][col + 1].write(ch);
+        }
+        self.buffer.chars[row][self.column_position].write(ScreenChar {
+            ascii_character: byte,
+            color_code: self.color_code,
+        });
+        self.line_lengths[row] = len + 1;
+        self.column_position += 1;
+        self.update_cursor();
+    }
+
+    /// Deletes the character immediately before the cursor, shifting the
+    /// tail of the line left to close the gap (mid-line backspace).
+    pub fn delete_before_cursor(&mut self) {
+        if self.column_position <= self.line_starts[self.row_position] {
+            return;
+        }
+        self.column_position -= 1;
+        self.delete_at_cursor();
+    }
+
+    /// Deletes the character at the cursor (Delete key), shifting the tail
+    /// of the line left. No-op at the end of the line.
+    pub fn delete_at_cursor(&mut self) {
+        let row = self.row_position;
+        let len = self.line_lengths[row];
+        if self.column_position >= len {
+            return;
+        }
+        for col in self.column_position..len - 1 {
+            let ch = self.buffer.chars[row][col + 1].read();
+            self.buffer.chars[row][col].write(ch);
+        }
+        self.buffer.chars[row][len - 1].write(ScreenChar {
+            ascii_character: b' ',
+            color_code: self.color_code,
+        });
+        self.line_lengths[row] = len - 1;
+        self.update_cursor();
+    }
+
+    /// Replaces the current line's visible text with `text` in place (used
+    /// to redraw the command line when history recall swaps it out from
+    /// under the cursor), clearing whatever was left over from a longer
+    /// previous line. Writes after the line's prompt (`line_starts`), not
+    /// at column 0, and leaves the cursor at the end of `text`.
+    pub fn redraw_line(&mut self, text: &str) {
+        let row = self.row_position;
+        let start = self.line_starts[row];
+        let clear_upto = self.line_lengths[row]
+            .max(start + text.len())
+            .min(BUFFER_WIDTH);
+        for col in start..clear_upto {
+            let byte = text.as_bytes().get(col - start).copied().unwrap_or(b' ');
+            self.buffer.chars[row][col].write(ScreenChar {
+                ascii_character: byte,
+                color_code: self.color_code,
+            });
+        }
+        self.column_position = (start + text.len()).min(BUFFER_WIDTH);
+        self.line_lengths[row] = self.column_position;
+        self.update_cursor();
+    }
 }
 
 impl fmt::Write for Writer {
@@ -179,12 +422,208 @@ impl fmt::Write for Writer {
     }
 }
 
+/// Maps ANSI SGR codes 0-7 (the `30-37`/`40-47` base offsets) to a VGA color.
+fn ansi_color(code: u16) -> Option<Color> {
+    Some(match code {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Brown, // ANSI calls this "yellow", VGA's dim yellow is Brown
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::LightGray,
+        _ => return None,
+    })
+}
+
+/// Maps ANSI SGR "bright" codes 0-7 (the `90-97`/`100-107` offsets) to a VGA color.
+fn ansi_bright_color(code: u16) -> Option<Color> {
+    Some(match code {
+        0 => Color::DarkGray,
+        1 => Color::LightRed,
+        2 => Color::LightGreen,
+        3 => Color::Yellow,
+        4 => Color::LightBlue,
+        5 => Color::Pink,
+        6 => Color::LightCyan,
+        7 => Color::White,
+        _ => return None,
+    })
+}
+
+/// Brightens a regular color to its `Light*` variant for SGR code 1 (bold).
+/// Colors that are already bright are left unchanged.
+fn brighten(color: Color) -> Color {
+    match color {
+        Color::Black => Color::DarkGray,
+        Color::Blue => Color::LightBlue,
+        Color::Green => Color::LightGreen,
+        Color::Cyan => Color::LightCyan,
+        Color::Red => Color::LightRed,
+        Color::Magenta => Color::Pink,
+        Color::Brown => Color::Yellow,
+        Color::LightGray => Color::White,
+        other => other,
+    }
+}
+
+/// Reverse of `ansi_color`/`ansi_bright_color`: which SGR base offset (0-7)
+/// and brightness a `Color` is reached through.
+fn sgr_base_code(color: Color) -> (bool, u16) {
+    match color {
+        Color::Black => (false, 0),
+        Color::Red => (false, 1),
+        Color::Green => (false, 2),
+        Color::Brown => (false, 3),
+        Color::Blue => (false, 4),
+        Color::Magenta => (false, 5),
+        Color::Cyan => (false, 6),
+        Color::LightGray => (false, 7),
+        Color::DarkGray => (true, 0),
+        Color::LightRed => (true, 1),
+        Color::LightGreen => (true, 2),
+        Color::Yellow => (true, 3),
+        Color::LightBlue => (true, 4),
+        Color::Pink => (true, 5),
+        Color::LightCyan => (true, 6),
+        Color::White => (true, 7),
+    }
+}
+
+/// A named style that renders to an SGR escape sequence (inspired by
+/// MOROS's `console::Style`). `{}`-formatting one writes plain ANSI codes,
+/// so it flows through ordinary `print!`/`println!`, gets parsed back out
+/// by this file's own `handle_ansi_byte` state machine on VGA, and carries
+/// through unmodified to `SERIAL1` as real ANSI for any terminal reading it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Style {
+    foreground: Option<Color>,
+    background: Option<Color>,
+}
+
+impl Style {
+    pub fn foreground(name: &str) -> Style {
+        Style { foreground: palette::lookup(name), background: None }
+    }
+
+    pub fn with_background(mut self, name: &str) -> Style {
+        self.background = palette::lookup(name);
+        self
+    }
+
+    pub fn reset() -> Style {
+        Style { foreground: None, background: None }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.foreground.is_none() && self.background.is_none() {
+            return write!(f, "\x1b[0m");
+        }
+        write!(f, "\x1b[")?;
+        let mut wrote = false;
+        if let Some(c) = self.foreground {
+            let (bright, base) = sgr_base_code(c);
+            write!(f, "{}", if bright { 90 + base } else { 30 + base })?;
+            wrote = true;
+        }
+        if let Some(c) = self.background {
+            if wrote {
+                write!(f, ";")?;
+            }
+            let (bright, base) = sgr_base_code(c);
+            write!(f, "{}", if bright { 100 + base } else { 40 + base })?;
+        }
+        write!(f, "m")
+    }
+}
+
+/// Named 16-color palette, remappable at runtime via `palette::from_csv` so
+/// a RAMFS config file can give the VGA colors (or close RGB approximations
+/// of them) custom names for `Style::foreground`/`with_background` to use.
+pub mod palette {
+    use super::Color;
+    use super::{BTreeMap, String};
+    use spin::Mutex;
+    use lazy_static::lazy_static;
+
+    lazy_static! {
+        static ref PALETTE: Mutex<BTreeMap<String, Color>> = Mutex::new(default_entries());
+    }
+
+    fn default_entries() -> BTreeMap<String, Color> {
+        let mut entries = BTreeMap::new();
+        for &(name, color) in BASE_COLORS {
+            entries.insert(String::from(name), color);
+        }
+        entries
+    }
+
+    /// The 16 VGA colors under their canonical lowercase names; also the
+    /// vocabulary `from_csv` accepts on the right-hand side of a remapping.
+    const BASE_COLORS: &[(&str, Color)] = &[
+        ("black", Color::Black), ("blue", Color::Blue), ("green", Color::Green),
+        ("cyan", Color::Cyan), ("red", Color::Red), ("magenta", Color::Magenta),
+        ("brown", Color::Brown), ("lightgray", Color::LightGray),
+        ("darkgray", Color::DarkGray), ("lightblue", Color::LightBlue),
+        ("lightgreen", Color::LightGreen), ("lightcyan", Color::LightCyan),
+        ("lightred", Color::LightRed), ("pink", Color::Pink),
+        ("yellow", Color::Yellow), ("white", Color::White),
+    ];
+
+    fn base_color(name: &str) -> Option<Color> {
+        BASE_COLORS.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+    }
+
+    /// Looks up `name` in the current palette, falling back to the base
+    /// color table so unremapped names (and the 16 canonical names
+    /// themselves) always resolve.
+    pub fn lookup(name: &str) -> Option<Color> {
+        PALETTE.lock().get(name).copied().or_else(|| base_color(name))
+    }
+
+    /// Parses `name,color` lines — e.g. `error,red` or `ocean,lightblue` —
+    /// remapping each `name` to the named base VGA color. Blank lines and
+    /// lines with an unrecognized color are skipped. Returns how many
+    /// remappings were applied.
+    pub fn from_csv(csv: &str) -> usize {
+        let mut palette = PALETTE.lock();
+        let mut applied = 0;
+        for line in csv.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut parts = line.splitn(2, ',');
+            let name = parts.next().unwrap_or("").trim();
+            let color_name = parts.next().unwrap_or("").trim();
+            if name.is_empty() {
+                continue;
+            }
+            if let Some(color) = base_color(color_name) {
+                palette.insert(String::from(name), color);
+                applied += 1;
+            }
+        }
+        applied
+    }
+}
+
 lazy_static! {
     pub static ref WRITER: Mutex<Writer> = Mutex::new(Writer {
         column_position: 0,
         row_position: 0,
         line_lengths: [0; BUFFER_HEIGHT],
-        color_code: ColorCode::new(Color::LightGreen, Color::Black),
+        line_starts: [0; BUFFER_HEIGHT],
+        color_code: ColorCode::new(DEFAULT_FOREGROUND, DEFAULT_BACKGROUND),
+        foreground: DEFAULT_FOREGROUND,
+        background: DEFAULT_BACKGROUND,
+        bold: false,
+        ansi_state: AnsiState::Ground,
+        ansi_params: Vec::new(),
+        ansi_current: None,
         buffer: unsafe { &mut *(0xb8000 as *mut Buffer) },
     });
 }
@@ -207,6 +646,62 @@ pub fn print_char(c: char) {
     });
 }
 
+/// Free-function wrappers around the `Writer` cursor/editing methods, for
+/// the shell's line editor — mirrors `backspace()` above.
+pub fn mark_line_start() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().mark_line_start();
+    });
+}
+
+pub fn cursor_left() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().cursor_left();
+    });
+}
+
+pub fn cursor_right() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().cursor_right();
+    });
+}
+
+pub fn cursor_home() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().cursor_home();
+    });
+}
+
+pub fn cursor_end() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().cursor_end();
+    });
+}
+
+pub fn insert_at_cursor(c: char) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().insert_at_cursor(c as u8);
+    });
+}
+
+pub fn delete_before_cursor() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().delete_before_cursor();
+    });
+}
+
+pub fn delete_at_cursor() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().delete_at_cursor();
+    });
+}
+
+pub fn redraw_line(text: &str) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().redraw_line(text);
+    });
+}
+
 #[macro_export]
 macro_rules! print {
     ($($arg:tt)*) => ($crate::vga_buffer::_print(format_args!($($arg)*)));
@@ -225,4 +720,11 @@ pub fn _print(args: fmt::Arguments) {
         let mut writer = WRITER.lock();
         writer.write_fmt(args).unwrap();
     });
+
+    // Once something has actually driven the shell over serial, mirror
+    // everything there too so a `-serial stdio` session sees real output
+    // instead of just the VGA framebuffer.
+    if crate::serial::console_active() {
+        crate::serial::_print(args);
+    }
 }
\ No newline at end of file